@@ -0,0 +1,247 @@
+use crate::error::{PointGuardError, Result};
+use anyhow::anyhow;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One layer of a Mercurial-style config: a flat map of `section.key` to
+/// value, plus the keys it explicitly `%unset` so a later merge removes
+/// them even though an earlier layer set them.
+#[derive(Debug, Default, Clone)]
+pub struct ConfigLayer {
+    values: HashMap<String, String>,
+    unset: Vec<String>,
+}
+
+impl ConfigLayer {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Merges `other` on top of `self`: `other`'s values win, and anything
+    /// `other` `%unset` is removed even if `self` set it. `other`'s unset
+    /// keys carry forward into the result (unless `other` also re-set them)
+    /// so a transitive `%include` chain's unsets survive further merges.
+    pub fn merge(mut self, other: ConfigLayer) -> ConfigLayer {
+        for key in &other.unset {
+            self.values.remove(key);
+        }
+        for key in &other.unset {
+            if !other.values.contains_key(key) && !self.unset.contains(key) {
+                self.unset.push(key.clone());
+            }
+        }
+        self.unset.retain(|key| !other.values.contains_key(key));
+        self.values.extend(other.values);
+        self
+    }
+}
+
+fn malformed(path: &Path, line: usize, message: &str) -> PointGuardError {
+    PointGuardError::Other(anyhow!("{}:{}: {}", path.display(), line, message))
+}
+
+fn qualify(section: &str, key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", section, key)
+    }
+}
+
+fn resolve_include(from: &Path, included: &str) -> PathBuf {
+    let included = Path::new(included.trim());
+    if included.is_absolute() {
+        included.to_path_buf()
+    } else {
+        from.parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(included)
+    }
+}
+
+/// Loads `path` into a `ConfigLayer`, recursively merging any `%include`d
+/// files on top of it as they're encountered, so a base config can be
+/// overridden by a machine-specific fragment.
+pub fn load_file(path: &Path) -> Result<ConfigLayer> {
+    load_file_chained(path, &mut Vec::new())
+}
+
+/// Like `load_file`, but tracks the canonicalized chain of files currently
+/// being included so a `%include` cycle is reported as an error instead of
+/// recursing forever.
+fn load_file_chained(path: &Path, chain: &mut Vec<PathBuf>) -> Result<ConfigLayer> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| PointGuardError::Other(anyhow!("{}: {}", path.display(), e)))?;
+    if chain.contains(&canonical) {
+        return Err(PointGuardError::Other(anyhow!(
+            "%include cycle detected at {}",
+            path.display()
+        )));
+    }
+    chain.push(canonical);
+    let result = fs::read_to_string(path)
+        .map_err(|e| PointGuardError::Other(anyhow!("{}: {}", path.display(), e)))
+        .and_then(|contents| parse(&contents, path, chain));
+    chain.pop();
+    result
+}
+
+fn parse(contents: &str, path: &Path, chain: &mut Vec<PathBuf>) -> Result<ConfigLayer> {
+    let section_re = Regex::new(r"^\[(?P<section>[^\]]+)\]\s*$").unwrap();
+    let kv_re = Regex::new(r"^(?P<key>[^=\s][^=]*?)\s*=\s*(?P<value>.*)$").unwrap();
+    let include_re = Regex::new(r"^%include\s+(?P<path>.+)$").unwrap();
+    let unset_re = Regex::new(r"^%unset\s+(?P<key>\S+)$").unwrap();
+
+    let mut layer = ConfigLayer::default();
+    let mut section = String::new();
+    let mut current_key: Option<String> = None;
+
+    for (number, raw_line) in contents.lines().enumerate() {
+        let line_number = number + 1;
+        if raw_line.trim().is_empty() || raw_line.trim_start().starts_with('#') {
+            current_key = None;
+            continue;
+        }
+        if raw_line.starts_with(char::is_whitespace) {
+            let key = current_key
+                .as_ref()
+                .ok_or_else(|| malformed(path, line_number, "continuation line has no preceding key"))?;
+            let existing = layer.values.get_mut(key).ok_or_else(|| {
+                malformed(path, line_number, "continuation line has no preceding key")
+            })?;
+            existing.push(' ');
+            existing.push_str(raw_line.trim());
+            continue;
+        }
+
+        let line = raw_line.trim();
+        if let Some(caps) = section_re.captures(line) {
+            section = caps["section"].to_string();
+            current_key = None;
+        } else if let Some(caps) = include_re.captures(line) {
+            let included = resolve_include(path, &caps["path"]);
+            let included_layer = load_file_chained(&included, chain)?;
+            layer = layer.merge(included_layer);
+            current_key = None;
+        } else if let Some(caps) = unset_re.captures(line) {
+            let key = qualify(&section, &caps["key"]);
+            layer.values.remove(&key);
+            layer.unset.push(key);
+            current_key = None;
+        } else if let Some(caps) = kv_re.captures(line) {
+            let key = qualify(&section, caps["key"].trim());
+            layer.values.insert(key.clone(), caps["value"].to_string());
+            current_key = Some(key);
+        } else {
+            return Err(malformed(
+                path,
+                line_number,
+                &format!("unrecognized config line: {}", line),
+            ));
+        }
+    }
+    Ok(layer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualify_combines_section_and_key() {
+        assert_eq!(qualify("", "dir"), "dir");
+        assert_eq!(qualify("pointguard", "dir"), "pointguard.dir");
+    }
+
+    #[test]
+    fn continuation_line_appends_to_previous_value() {
+        let layer = parse(
+            "[pointguard]\neditor = vim\n  --noplugin\n",
+            Path::new("test.conf"),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(layer.get("pointguard.editor"), Some("vim --noplugin"));
+    }
+
+    #[test]
+    fn unset_removes_a_key_inherited_from_an_earlier_layer() {
+        let base = parse(
+            "[pointguard]\neditor = vim\n",
+            Path::new("base.conf"),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let override_layer = parse(
+            "[pointguard]\n%unset editor\n",
+            Path::new("override.conf"),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let merged = base.merge(override_layer);
+        assert_eq!(merged.get("pointguard.editor"), None);
+    }
+
+    #[test]
+    fn include_merges_on_top_so_the_included_file_wins() {
+        let dir =
+            std::env::temp_dir().join(format!("pointguard-config-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("main.conf"),
+            "[pointguard]\ndir = /base\neditor = vim\n%include override.conf\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("override.conf"), "[pointguard]\neditor = nano\n").unwrap();
+
+        let layer = load_file(&dir.join("main.conf")).unwrap();
+        assert_eq!(layer.get("pointguard.editor"), Some("nano"));
+        assert_eq!(layer.get("pointguard.dir"), Some("/base"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unset_survives_a_transitive_include_chain() {
+        let dir = std::env::temp_dir()
+            .join(format!("pointguard-config-transitive-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("main.conf"),
+            "[pointguard]\ndir = /base\n%include layer1.conf\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("layer1.conf"), "[pointguard]\n%include layer2.conf\n").unwrap();
+        std::fs::write(dir.join("layer2.conf"), "[pointguard]\n%unset dir\n").unwrap();
+
+        let layer = load_file(&dir.join("main.conf")).unwrap();
+        assert_eq!(layer.get("pointguard.dir"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn include_cycle_is_reported_as_an_error() {
+        let dir =
+            std::env::temp_dir().join(format!("pointguard-config-cycle-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.conf"), "%include b.conf\n").unwrap();
+        std::fs::write(dir.join("b.conf"), "%include a.conf\n").unwrap();
+
+        let result = load_file(&dir.join("a.conf"));
+        assert!(result.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn malformed_line_reports_file_and_line_number() {
+        let err = parse("not a valid line\n", Path::new("bad.conf"), &mut Vec::new()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("bad.conf"));
+        assert!(message.contains(":1:"));
+    }
+}
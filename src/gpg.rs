@@ -0,0 +1,20 @@
+use crate::error::{PointGuardError, Result};
+use anyhow::anyhow;
+use std::path::Path;
+use std::process::Command;
+
+pub fn decrypt(path: &Path) -> Result<String> {
+    let output = Command::new("gpg")
+        .arg("--quiet")
+        .arg("--decrypt")
+        .arg(path)
+        .output()?;
+    if !output.status.success() {
+        return Err(PointGuardError::Other(anyhow!(
+            "gpg failed to decrypt {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
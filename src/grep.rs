@@ -0,0 +1,132 @@
+use crate::error::Result;
+use crate::gpg;
+use crate::opts::Grep;
+use crate::settings::Settings;
+use crate::show::is_hidden;
+use regex::Regex;
+use std::io;
+use walkdir::WalkDir;
+use zeroize::Zeroize;
+
+/// A `grep` pattern: a regex if it compiles as one, otherwise a plain
+/// substring.
+enum Pattern {
+    Regex(Regex),
+    Substring(String),
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Self {
+        match Regex::new(pattern) {
+            Ok(re) => Pattern::Regex(re),
+            Err(_) => Pattern::Substring(pattern.to_string()),
+        }
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        match self {
+            Pattern::Regex(re) => re.is_match(line),
+            Pattern::Substring(needle) => line.contains(needle.as_str()),
+        }
+    }
+}
+
+/// Decrypts every `.gpg` entry under `settings.dir` and prints the lines of
+/// its body matching `opts.pattern`, grouped under each entry's display
+/// path. Because this decrypts the whole store, a failure to decrypt one
+/// entry is collected rather than aborting the scan, and reported at the
+/// end.
+pub fn grep(buffer: &mut dyn io::Write, opts: Grep, settings: Settings) -> Result<()> {
+    let pattern = Pattern::new(&opts.pattern);
+    let mut errors = Vec::new();
+    for entry in WalkDir::new(&settings.dir)
+        .into_iter()
+        .filter_entry(|e| !is_hidden(e))
+        .filter_map(|entry| entry.ok())
+    {
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(|ext| ext.to_str()) != Some("gpg") {
+            continue;
+        }
+        let mut body = match gpg::decrypt(path) {
+            Ok(body) => body,
+            Err(e) => {
+                errors.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+        let display_path = path
+            .strip_prefix(&settings.dir)
+            .unwrap_or(path)
+            .with_extension("");
+        let mut header_written = false;
+        for line in body.lines() {
+            if pattern.matches(line) {
+                if !header_written {
+                    writeln!(buffer, "{}:", display_path.display())?;
+                    header_written = true;
+                }
+                writeln!(buffer, "  {}", line)?;
+            }
+        }
+        body.zeroize();
+    }
+    if !errors.is_empty() {
+        writeln!(buffer, "\nErrors:")?;
+        for error in &errors {
+            writeln!(buffer, "  {}", error)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+    use std::path::PathBuf;
+
+    fn get_test_settings(dir: PathBuf) -> Settings {
+        Settings {
+            dir,
+            clip_time: 45,
+            generated_length: 25,
+            editor: String::from("vim"),
+            follow_links: false,
+        }
+    }
+
+    #[test]
+    fn pattern_matches_substring_and_regex() {
+        let substring = Pattern::new("wor");
+        assert!(substring.matches("password"));
+        assert!(!substring.matches("secret"));
+
+        let regex = Pattern::new("^[A-Z][a-z]+$");
+        assert!(regex.matches("Password"));
+        assert!(!regex.matches("password"));
+    }
+
+    #[test]
+    fn grep_reports_decrypt_errors_without_aborting_the_scan() {
+        let dir =
+            std::env::temp_dir().join(format!("pointguard-grep-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.gpg"), b"not a valid gpg file").unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        grep(
+            &mut result,
+            Grep {
+                pattern: String::from("anything"),
+            },
+            get_test_settings(dir.clone()),
+        )
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        assert!(result_string.contains("Errors:"));
+        assert!(result_string.contains("broken.gpg"));
+    }
+}
@@ -0,0 +1,37 @@
+use clap::Args;
+
+#[derive(Args, Debug, Clone)]
+pub struct Show {
+    /// Name of the entry to show; prints the whole tree if omitted
+    pub input: Option<String>,
+
+    /// Copy the password to the clipboard instead of printing it
+    #[arg(short, long)]
+    pub clip: bool,
+
+    /// Follow symlinked entries and subtrees, overriding the `follow_links` setting
+    #[arg(long)]
+    pub follow_links: bool,
+}
+
+impl Show {
+    pub fn new(input: Option<String>) -> Self {
+        Self {
+            input,
+            clip: false,
+            follow_links: false,
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Find {
+    /// Query to match entry names against (substring, case-insensitive, or a regex)
+    pub query: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Grep {
+    /// Pattern to search decrypted entry bodies for (substring or a regex)
+    pub pattern: String,
+}
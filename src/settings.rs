@@ -0,0 +1,110 @@
+use crate::config::{self, ConfigLayer};
+use crate::error::Result;
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub dir: PathBuf,
+    pub clip_time: u64,
+    pub generated_length: usize,
+    pub editor: String,
+    pub follow_links: bool,
+}
+
+impl Settings {
+    fn defaults() -> Settings {
+        let home = env::var("HOME").map(PathBuf::from).unwrap_or_default();
+        Settings {
+            dir: home.join(".password-store"),
+            clip_time: 45,
+            generated_length: 25,
+            editor: String::from("vim"),
+            follow_links: false,
+        }
+    }
+
+    /// Builds settings by layering, in order: built-in defaults, the config
+    /// file at `global_config` (which may itself `%include` further
+    /// fragments and `%unset` inherited keys), and environment overrides.
+    /// Later layers win.
+    pub fn load(global_config: &Path) -> Result<Settings> {
+        let mut settings = Settings::defaults();
+        if global_config.exists() {
+            let layer = config::load_file(global_config)?;
+            settings.apply(&layer);
+        }
+        settings.apply_env();
+        Ok(settings)
+    }
+
+    fn apply(&mut self, layer: &ConfigLayer) {
+        if let Some(dir) = layer.get("pointguard.dir") {
+            self.dir = PathBuf::from(dir);
+        }
+        if let Some(clip_time) = layer
+            .get("pointguard.clip_time")
+            .and_then(|v| v.parse().ok())
+        {
+            self.clip_time = clip_time;
+        }
+        if let Some(generated_length) = layer
+            .get("pointguard.generated_length")
+            .and_then(|v| v.parse().ok())
+        {
+            self.generated_length = generated_length;
+        }
+        if let Some(editor) = layer.get("pointguard.editor") {
+            self.editor = editor.to_string();
+        }
+        if let Some(follow_links) = layer
+            .get("pointguard.follow_links")
+            .and_then(|v| v.parse().ok())
+        {
+            self.follow_links = follow_links;
+        }
+    }
+
+    fn apply_env(&mut self) {
+        if let Ok(dir) = env::var("POINTGUARD_DIR") {
+            self.dir = PathBuf::from(dir);
+        }
+        if let Ok(editor) = env::var("EDITOR") {
+            self.editor = editor;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_applies_config_file_on_top_of_defaults() {
+        let dir = std::env::temp_dir().join(format!(
+            "pointguard-settings-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config");
+        std::fs::write(
+            &config_path,
+            "[pointguard]\nclip_time = 10\nfollow_links = true\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(&config_path).unwrap();
+        assert_eq!(settings.clip_time, 10);
+        assert!(settings.follow_links);
+        assert_eq!(settings.editor, "vim");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_falls_back_to_defaults_when_no_config_file_exists() {
+        let settings = Settings::load(Path::new("/nonexistent/pointguard/config")).unwrap();
+        assert_eq!(settings.clip_time, 45);
+        assert_eq!(settings.editor, "vim");
+    }
+}
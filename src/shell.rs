@@ -0,0 +1,208 @@
+use crate::error::{PointGuardError, Result};
+use crate::opts::Show;
+use crate::settings::Settings;
+use crate::show;
+use anyhow::anyhow;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::history::DefaultHistory;
+use rustyline::{Context, Editor, Helper};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Completes entry and directory names from the on-disk layout of the
+/// completer's current directory.
+struct EntryCompleter {
+    dir: Rc<RefCell<PathBuf>>,
+}
+
+impl Completer for EntryCompleter {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(' ').map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..pos];
+        let mut candidates = Vec::new();
+        if let Ok(read_dir) = std::fs::read_dir(&*self.dir.borrow()) {
+            for entry in read_dir.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let name = name.strip_suffix(".gpg").map(str::to_owned).unwrap_or(name);
+                if name.starts_with(word) {
+                    candidates.push(Pair {
+                        display: name.clone(),
+                        replacement: name,
+                    });
+                }
+            }
+        }
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for EntryCompleter {
+    type Hint = String;
+}
+impl Highlighter for EntryCompleter {}
+impl Validator for EntryCompleter {}
+impl Helper for EntryCompleter {}
+
+/// Resolves `arg` (relative, absolute from the store root, or `..`) against
+/// `current`, validating it names a directory inside `root`.
+fn resolve_dir(root: &Path, current: &Path, arg: &str) -> Result<PathBuf> {
+    let target = if arg.is_empty() || arg == "/" {
+        root.to_path_buf()
+    } else if let Some(rest) = arg.strip_prefix('/') {
+        root.join(rest)
+    } else {
+        current.join(arg)
+    };
+    let canonical_root = root.canonicalize()?;
+    let canonical_target = target
+        .canonicalize()
+        .map_err(|_| PointGuardError::Other(anyhow!("{} is not a directory in the store", arg)))?;
+    if !canonical_target.starts_with(&canonical_root) || !canonical_target.is_dir() {
+        return Err(PointGuardError::Other(anyhow!(
+            "{} is not a directory in the store",
+            arg
+        )));
+    }
+    // Rejoin the canonicalized, store-relative path onto `root` rather than
+    // returning `target` as-is, so `..` segments don't accumulate in
+    // `current` across repeated `cd`s.
+    let relative = canonical_target
+        .strip_prefix(&canonical_root)
+        .unwrap_or_else(|_| Path::new(""));
+    Ok(root.join(relative))
+}
+
+/// Turns `name`, looked up relative to the shell's current directory, into
+/// the path `show` expects relative to `settings.dir`.
+fn resolve_name(root: &Path, current: &Path, name: &str) -> String {
+    let relative = current.strip_prefix(root).unwrap_or_else(|_| Path::new(""));
+    if relative.as_os_str().is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", relative.display(), name)
+    }
+}
+
+/// Launches an interactive `pointguard:/dir>` shell for browsing the store.
+pub fn shell(settings: Settings) -> Result<()> {
+    let current = Rc::new(RefCell::new(settings.dir.clone()));
+    let mut editor = Editor::<EntryCompleter, DefaultHistory>::new()
+        .map_err(|e| PointGuardError::Other(anyhow!(e)))?;
+    editor.set_helper(Some(EntryCompleter {
+        dir: current.clone(),
+    }));
+
+    let stdout = io::stdout();
+    loop {
+        let relative = current
+            .borrow()
+            .strip_prefix(&settings.dir)
+            .unwrap_or_else(|_| Path::new(""))
+            .display()
+            .to_string();
+        let prompt = format!("pointguard:/{}> ", relative);
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(PointGuardError::Other(anyhow!(e))),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let mut parts = line.splitn(2, ' ');
+        let command = parts.next().unwrap_or("");
+        let arg = parts.next().unwrap_or("").trim();
+        let mut buffer = stdout.lock();
+
+        let result = match command {
+            "exit" | "quit" => break,
+            "ls" => show::print_tree(
+                &mut buffer,
+                &current.borrow(),
+                None,
+                None,
+                settings.follow_links,
+            ),
+            "cd" => {
+                let resolved = resolve_dir(&settings.dir, &current.borrow(), arg);
+                match resolved {
+                    Ok(path) => {
+                        *current.borrow_mut() = path;
+                        Ok(())
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            "show" => show::show(
+                &mut buffer,
+                Show::new(Some(resolve_name(&settings.dir, &current.borrow(), arg))),
+                settings.clone(),
+            ),
+            "clip" => {
+                let mut opts = Show::new(Some(resolve_name(&settings.dir, &current.borrow(), arg)));
+                opts.clip = true;
+                show::show(&mut buffer, opts, settings.clone())
+            }
+            other => Err(PointGuardError::Other(anyhow!("Unknown command: {}", other))),
+        };
+        if let Err(e) = result {
+            writeln!(buffer, "{}", e)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_store() -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pointguard-shell-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("work")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn resolve_dir_normalizes_dot_dot_back_to_root() {
+        let root = make_store();
+        let work = resolve_dir(&root, &root, "work").unwrap();
+        assert_eq!(work, root.join("work"));
+        let back = resolve_dir(&root, &work, "..").unwrap();
+        assert_eq!(back, root);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_dir_rejects_paths_that_escape_the_store() {
+        let root = make_store();
+        assert!(resolve_dir(&root, &root, "..").is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_name_joins_relative_to_current() {
+        let root = PathBuf::from("/store");
+        assert_eq!(resolve_name(&root, &root, "entry"), "entry");
+        assert_eq!(
+            resolve_name(&root, &root.join("work"), "entry"),
+            "work/entry"
+        );
+    }
+}
@@ -1,21 +1,101 @@
 use crate::error::{PointGuardError, Result};
 use crate::gpg;
-use crate::opts::Show;
+use crate::opts::{Find, Show};
 use crate::settings::Settings;
 use anyhow::anyhow;
 use ptree::output;
+use regex::{Regex, RegexBuilder};
 use std::{
+    collections::HashSet,
     env,
     io::{self, Write},
-    path::Path,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
+use terminal_size::{terminal_size, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use walkdir::{DirEntry, WalkDir};
 
 mod pgtree;
 use pgtree::TreeBuilder;
 
-fn is_hidden(entry: &DirEntry) -> bool {
+/// A `find` query: a case-insensitive regex if the query string compiles as
+/// one, otherwise a case-insensitive substring, mirroring how `pass find`
+/// matches entry names.
+pub(crate) enum Query {
+    Regex(Regex),
+    Substring(String),
+}
+
+impl Query {
+    fn new(query: &str) -> Self {
+        match RegexBuilder::new(query).case_insensitive(true).build() {
+            Ok(re) => Query::Regex(re),
+            Err(_) => Query::Substring(query.to_lowercase()),
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Query::Regex(re) => re.is_match(name),
+            Query::Substring(needle) => name.to_lowercase().contains(needle.as_str()),
+        }
+    }
+}
+
+/// Walks `entries` for leaves matching `query`, then marks every ancestor
+/// directory of a match so the tree can be pruned down to just the matches
+/// and the path needed to reach them.
+fn retained_paths(root: &Path, entries: &[DirEntry], query: &Query) -> HashSet<PathBuf> {
+    let mut kept = HashSet::new();
+    for entry in entries {
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let name = match relative_match_path(root, entry.path()) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        if !query.matches(&name) {
+            continue;
+        }
+        let mut node = entry.path();
+        kept.insert(node.to_path_buf());
+        while let Some(parent) = node.parent() {
+            if !kept.insert(parent.to_path_buf()) {
+                break;
+            }
+            node = parent;
+        }
+    }
+    kept
+}
+
+/// Truncates `name` with an ellipsis so it fits the terminal width at the
+/// given tree depth, rather than letting a long entry name wrap mid-branch.
+fn fit_to_width(name: &str, depth: usize) -> String {
+    let columns = terminal_size()
+        .map(|(Width(w), _)| w as usize)
+        .unwrap_or(80);
+    let available = columns.saturating_sub(depth * 4 + 4).max(8);
+    if name.width() <= available {
+        return name.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for ch in name.chars() {
+        let w = ch.width().unwrap_or(0);
+        if used + w > available.saturating_sub(1) {
+            break;
+        }
+        truncated.push(ch);
+        used += w;
+    }
+    truncated.push('…');
+    truncated
+}
+
+pub(crate) fn is_hidden(entry: &DirEntry) -> bool {
     entry
         .file_name()
         .to_str()
@@ -34,31 +114,104 @@ fn display_path(path: &Path) -> Result<String> {
         .to_string())
 }
 
-fn print_tree(buffer: &mut dyn io::Write, path: &Path, input: Option<String>) -> Result<()> {
+/// Builds the path `find` matches against: `path` relative to `root` with
+/// the leaf's extension dropped (e.g. `work/gmail.gpg` -> `work/gmail`), so
+/// a query like `work` matches everything under a `work` directory and not
+/// just a leaf literally named `work`.
+fn relative_match_path(root: &Path, path: &Path) -> Result<String> {
+    let stem = display_path(path)?;
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let with_stem = match relative.parent() {
+        Some(parent) => parent.join(stem),
+        None => PathBuf::from(stem),
+    };
+    Ok(with_stem.display().to_string())
+}
+
+/// Renders `target`'s display name relative to `root` when possible (e.g.
+/// `work/gmail`), falling back to the absolute path for targets that escape
+/// the tree being printed.
+fn relative_display(root: &Path, target: &Path) -> String {
+    match target.strip_prefix(root) {
+        Ok(relative) if relative.extension().map_or(false, |ext| ext == "gpg") => {
+            relative.with_extension("").display().to_string()
+        }
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => target.display().to_string(),
+    }
+}
+
+pub(crate) fn print_tree(
+    buffer: &mut dyn io::Write,
+    path: &Path,
+    input: Option<String>,
+    query: Option<&Query>,
+    follow_links: bool,
+) -> Result<()> {
     let mut builder =
         TreeBuilder::new(input.unwrap_or_else(|| String::from("Point Guard Password Store")));
-    let walker = WalkDir::new(&path).into_iter();
+    let canonical_root = path.canonicalize().ok();
+    // Canonicalized directories currently open on the path from the root to
+    // the entry being considered, paired with their depth. A followed
+    // symlink whose target is already on this ancestor chain is a cycle;
+    // one pointing elsewhere (even at the same canonical target reached via
+    // a different path) is a legitimate alias and must still be descended.
+    let mut open_dirs: Vec<(usize, PathBuf)> = Vec::new();
+    let entries: Vec<DirEntry> = WalkDir::new(path)
+        .follow_links(follow_links)
+        .into_iter()
+        .filter_entry(|e| {
+            if is_hidden(e) {
+                return false;
+            }
+            if !follow_links || !e.file_type().is_dir() {
+                return true;
+            }
+            let depth = e.depth();
+            while open_dirs.last().map_or(false, |(d, _)| *d >= depth) {
+                open_dirs.pop();
+            }
+            match e.path().canonicalize() {
+                Ok(canonical) => {
+                    if open_dirs.iter().any(|(_, dir)| *dir == canonical) {
+                        false
+                    } else {
+                        open_dirs.push((depth, canonical));
+                        true
+                    }
+                }
+                Err(_) => false,
+            }
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.depth() > 0)
+        .collect();
+    let kept = query.map(|query| retained_paths(path, &entries, query));
     let mut depth = 1;
-    for entry in walker.filter_entry(|e| !is_hidden(e)) {
-        let entry = match entry {
-            Ok(entry) => entry,
-            // TODO: should this return an error?
-            Err(_e) => continue,
-        };
-        if entry.depth() == 0 {
-            continue;
+    for entry in &entries {
+        if let Some(kept) = &kept {
+            if !kept.contains(entry.path()) {
+                continue;
+            }
         }
         let path = entry.path();
-        if path.is_dir() {
-            builder.begin_child(display_path(path)?);
-            depth += 1;
-        } else if entry.depth() == depth {
-            builder.add_empty_child(display_path(path)?);
-        } else {
+        let mut name = display_path(path)?;
+        if follow_links && entry.path_is_symlink() {
+            if let (Ok(target), Some(root)) = (path.canonicalize(), &canonical_root) {
+                name = format!("{} -> {}", name, relative_display(root, &target));
+            }
+        }
+        let name = fit_to_width(&name, entry.depth());
+        while depth > entry.depth() {
             builder.end_child();
-            builder.add_empty_child(display_path(path)?);
             depth -= 1;
         }
+        if entry.file_type().is_dir() {
+            builder.begin_child(name);
+            depth += 1;
+        } else {
+            builder.add_empty_child(name);
+        }
     }
     let mut root = builder.build();
     root.sort();
@@ -66,15 +219,31 @@ fn print_tree(buffer: &mut dyn io::Write, path: &Path, input: Option<String>) ->
     Ok(())
 }
 
+/// `target` may reach outside the store via a symlinked ancestor directory,
+/// not just by being a symlink itself, so canonicalize the whole resolved
+/// path rather than checking `target.is_symlink()`.
+fn reject_if_outside_store(target: &Path, root: &Path, name: &Option<String>) -> Result<()> {
+    let canonical_target = target.canonicalize()?;
+    let canonical_root = root.canonicalize()?;
+    if !canonical_target.starts_with(&canonical_root) {
+        return Err(PointGuardError::Other(anyhow!(
+            "{} links outside the password store",
+            name.clone().unwrap_or_else(|| String::from("entry"))
+        )));
+    }
+    Ok(())
+}
+
 pub fn show(buffer: &mut dyn io::Write, opts: Show, settings: Settings) -> Result<()> {
     let (path, file) = match &opts.input {
         Some(name) => (
             settings.dir.join(name),
             settings.dir.join(name.to_owned() + ".gpg"),
         ),
-        None => (settings.dir.clone(), settings.dir),
+        None => (settings.dir.clone(), settings.dir.clone()),
     };
     if file.exists() && !file.is_dir() {
+        reject_if_outside_store(&file, &settings.dir, &opts.input)?;
         let pw = gpg::decrypt(&file)?;
         if opts.clip {
             let exe = env::current_exe()?;
@@ -102,7 +271,9 @@ pub fn show(buffer: &mut dyn io::Write, opts: Show, settings: Settings) -> Resul
             Ok(())
         }
     } else if path.is_dir() {
-        print_tree(buffer, &path, opts.input)
+        reject_if_outside_store(&path, &settings.dir, &opts.input)?;
+        let follow_links = opts.follow_links || settings.follow_links;
+        print_tree(buffer, &path, opts.input, None, follow_links)
     } else {
         Err(io::Error::new(
             io::ErrorKind::NotFound,
@@ -115,6 +286,14 @@ pub fn show(buffer: &mut dyn io::Write, opts: Show, settings: Settings) -> Resul
     }
 }
 
+/// Prints a tree of every entry whose name matches `opts.query`, pruned down
+/// to the matches and the ancestor directories needed to reach them.
+pub fn find(buffer: &mut dyn io::Write, opts: Find, settings: Settings) -> Result<()> {
+    let query = Query::new(&opts.query);
+    let follow_links = settings.follow_links;
+    print_tree(buffer, &settings.dir, None, Some(&query), follow_links)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,6 +305,7 @@ mod tests {
             clip_time: 45,
             generated_length: 25,
             editor: String::from("vim"),
+            follow_links: false,
         }
     }
 
@@ -204,4 +384,178 @@ mod tests {
         assert!(result_string.contains("dir"));
         assert!(!result_string.contains("notinstore"));
     }
+
+    #[test]
+    fn find_prunes_tree_to_matches() {
+        let mut result: Vec<u8> = vec![];
+        find(
+            &mut result,
+            Find {
+                query: String::from("uniq"),
+            },
+            get_test_settings(),
+        )
+        .unwrap();
+        let result_string = String::from_utf8(result).unwrap();
+        assert!(result_string.contains("unique"));
+        assert!(!result_string.contains("notinstore"));
+        assert!(!result_string.contains("pointguard.dev"));
+    }
+
+    fn prefix_len(output: &str, label: &str) -> usize {
+        let line = output
+            .lines()
+            .find(|line| line.trim_end().ends_with(label))
+            .unwrap_or_else(|| panic!("no line ending in {} in:\n{}", label, output));
+        line.chars().take_while(|c| !c.is_alphanumeric()).count()
+    }
+
+    #[test]
+    fn find_closes_every_open_level_before_adding_a_shallower_match() {
+        let root = std::env::temp_dir().join(format!("pointguard-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::write(root.join("a/b/match1.gpg"), b"").unwrap();
+        std::fs::write(root.join("match2.gpg"), b"").unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        let query = Query::new("match");
+        print_tree(&mut result, &root, None, Some(&query), false).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        // `match2` sits at the root alongside `a`, so it must be rendered at
+        // the same depth as `a`, not nested inside it.
+        assert_eq!(prefix_len(&result_string, "a"), prefix_len(&result_string, "match2"));
+        assert!(prefix_len(&result_string, "match1") > prefix_len(&result_string, "a"));
+    }
+
+    #[test]
+    fn find_matches_a_directory_component_not_just_the_leaf_name() {
+        let root = std::env::temp_dir().join(format!("pointguard-test-dir-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("work")).unwrap();
+        std::fs::create_dir_all(root.join("personal")).unwrap();
+        std::fs::write(root.join("work/email.gpg"), b"").unwrap();
+        std::fs::write(root.join("personal/email.gpg"), b"").unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        let query = Query::new("work");
+        print_tree(&mut result, &root, None, Some(&query), false).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        assert!(result_string.contains("work"));
+        assert!(!result_string.contains("personal"));
+    }
+
+    #[test]
+    fn query_regex_branch_is_case_insensitive() {
+        let root = std::env::temp_dir().join(format!("pointguard-test-case-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("Email.gpg"), b"").unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        let query = Query::new("email");
+        print_tree(&mut result, &root, None, Some(&query), false).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        assert!(result_string.contains("Email"));
+    }
+
+    #[test]
+    fn print_tree_renders_a_symlinked_alias_alongside_the_real_entry() {
+        let root = std::env::temp_dir().join(format!("pointguard-test-alias-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("work")).unwrap();
+        std::fs::write(root.join("work/gmail.gpg"), b"").unwrap();
+        std::os::unix::fs::symlink(root.join("work/gmail.gpg"), root.join("email.gpg")).unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        print_tree(&mut result, &root, None, None, true).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        // The real entry and its alias both resolve to the same target, but
+        // a symlink cycle guard must only stop recursion into directories,
+        // not dedupe leaves, so both names still show up in the tree.
+        assert!(result_string.contains("gmail"));
+        assert!(result_string.contains("email -> "));
+    }
+
+    #[test]
+    fn print_tree_renders_a_sibling_directory_alias_not_just_an_ancestor() {
+        let root = std::env::temp_dir().join(format!("pointguard-test-dir-alias-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("real")).unwrap();
+        std::fs::write(root.join("real/secret.gpg"), b"").unwrap();
+        std::os::unix::fs::symlink(root.join("real"), root.join("alias")).unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        print_tree(&mut result, &root, None, None, true).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        // `alias` and `real` both canonicalize to the same directory, but
+        // neither is an ancestor of the other, so both must still be
+        // descended and show `secret` under them.
+        assert!(result_string.contains("real"));
+        assert!(result_string.contains("alias -> "));
+        assert_eq!(result_string.matches("secret").count(), 2);
+    }
+
+    #[test]
+    fn print_tree_stops_recursing_into_a_symlinked_ancestor_cycle() {
+        let root = std::env::temp_dir().join(format!("pointguard-test-cycle-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::write(root.join("a/entry.gpg"), b"").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("a/loop")).unwrap();
+
+        let mut result: Vec<u8> = vec![];
+        // A cycle that isn't guarded against would recurse until the OS
+        // symlink-loop limit kicks in; completing at all is the assertion.
+        print_tree(&mut result, &root, None, None, true).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result_string = String::from_utf8(result).unwrap();
+        assert!(result_string.contains("entry"));
+    }
+
+    #[test]
+    fn show_rejects_a_symlinked_directory_escaping_the_store() {
+        let dir = std::env::temp_dir().join(format!("pointguard-test-escape-dir-{}", std::process::id()));
+        let outside = std::env::temp_dir().join(format!("pointguard-test-outside-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("evil")).unwrap();
+
+        let mut settings = get_test_settings();
+        settings.dir = dir.clone();
+
+        let mut result: Vec<u8> = vec![];
+        let err = show(
+            &mut result,
+            Show::new(Some(String::from("evil"))),
+            settings,
+        )
+        .unwrap_err();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+
+        assert!(err.to_string().contains("outside the password store"));
+    }
+
+    #[test]
+    fn show_follows_symlinked_entry() {
+        let mut result: Vec<u8> = vec![];
+        show(
+            &mut result,
+            Show {
+                input: Some(String::from("email")),
+                clip: false,
+                follow_links: true,
+            },
+            get_test_settings(),
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(result).unwrap().trim(), "dir/test");
+    }
 }
@@ -0,0 +1,63 @@
+use ptree::item::StringItem;
+use ptree::{Style, TreeItem};
+use std::borrow::Cow;
+use std::io;
+
+/// Thin wrapper around `ptree::TreeBuilder` that adds alphabetical sorting,
+/// since upstream `ptree` renders children in insertion order.
+pub struct TreeBuilder {
+    inner: ptree::TreeBuilder,
+}
+
+impl TreeBuilder {
+    pub fn new(text: String) -> Self {
+        Self {
+            inner: ptree::TreeBuilder::new(text),
+        }
+    }
+
+    pub fn begin_child(&mut self, name: String) -> &mut Self {
+        self.inner.begin_child(name);
+        self
+    }
+
+    pub fn add_empty_child(&mut self, name: String) -> &mut Self {
+        self.inner.add_empty_child(name);
+        self
+    }
+
+    pub fn end_child(&mut self) -> &mut Self {
+        self.inner.end_child();
+        self
+    }
+
+    pub fn build(&mut self) -> Tree {
+        Tree(self.inner.build())
+    }
+}
+
+#[derive(Clone)]
+pub struct Tree(StringItem);
+
+impl Tree {
+    pub fn sort(&mut self) {
+        sort_children(&mut self.0);
+    }
+}
+
+fn sort_children(item: &mut StringItem) {
+    item.children.sort_by(|a, b| a.text.cmp(&b.text));
+    item.children.iter_mut().for_each(sort_children);
+}
+
+impl TreeItem for Tree {
+    type Child = StringItem;
+
+    fn write_self<W: io::Write>(&self, f: &mut W, style: &Style) -> io::Result<()> {
+        self.0.write_self(f, style)
+    }
+
+    fn children(&self) -> Cow<'_, [Self::Child]> {
+        self.0.children()
+    }
+}